@@ -59,44 +59,180 @@ pub trait Storable {
     fn load<S: Storage>(storage: &S, addr: u16) -> Self;
 }
 
+/// Number of rotating slots `State` is spread across. Each `store` writes
+/// to the next slot in the ring rather than the same cells every time, so
+/// a frequently-saved counter wears all `NUM_SLOTS` slots evenly instead
+/// of burning through one region of the ~100k-cycle EEPROM cells.
+const NUM_SLOTS: u16 = 8;
+
+/// Marks a slot as having been written by this firmware, as opposed to a
+/// fresh or corrupted chip whose bytes happen to look like a record.
+const SLOT_MAGIC: u8 = 0xc7;
+
+/// Schema version of the payload a slot holds, so a future layout change
+/// can tell old records apart instead of misreading them. Bumped each
+/// time `COUNTER_LEN` changes: 1 pre-`modulus`, 2 pre-`target`, 3 now.
+const STATE_SCHEMA_VERSION: u8 = 3;
+
+const SEQ_LEN: u16 = 2;
+const MAGIC_LEN: u16 = 1;
+const VERSION_LEN: u16 = 1;
+const CRC_LEN: u16 = 1;
+
+/// Size in bytes of the header written before each slot's payload: the
+/// sequence number, the magic byte, and the schema version.
+const SLOT_HEADER_LEN: u16 = SEQ_LEN + MAGIC_LEN + VERSION_LEN;
+
+/// Size in bytes of the serialized `Counters` payload stored in each slot.
+const SLOT_PAYLOAD_LEN: u16 = 24;
+
+const SLOT_LEN: u16 = SLOT_HEADER_LEN + SLOT_PAYLOAD_LEN + CRC_LEN;
+
+fn slot_addr(region_addr: u16, slot: u16) -> u16 {
+    region_addr + slot * SLOT_LEN
+}
+
+/// Computes a CRC-8 (polynomial 0x07) checksum over `data`. Used to tell
+/// a genuine stored record apart from a freshly-erased or corrupted chip
+/// before trusting the bytes read back out of it.
+pub fn checksum(data: &[u8]) -> u8 {
+    let mut crc: u8 = 0;
+
+    for &byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+
+    crc
+}
+
+/// Reads a slot's sequence number, returning `None` unless the magic
+/// byte, schema version, and payload CRC all check out.
+fn read_slot_seq<S: Storage>(storage: &S, region_addr: u16, slot: u16) -> Option<u16> {
+    let base = slot_addr(region_addr, slot);
+
+    let mut header = [0; SLOT_HEADER_LEN as usize];
+    storage.read_bytes(base, SLOT_HEADER_LEN as usize, &mut header);
+    if header[2] != SLOT_MAGIC || header[3] != STATE_SCHEMA_VERSION {
+        return None;
+    }
+
+    let mut payload = [0; SLOT_PAYLOAD_LEN as usize];
+    storage.read_bytes(
+        base + SLOT_HEADER_LEN,
+        SLOT_PAYLOAD_LEN as usize,
+        &mut payload,
+    );
+    let crc = storage.read_byte(base + SLOT_HEADER_LEN + SLOT_PAYLOAD_LEN);
+    if checksum(&payload) != crc {
+        return None;
+    }
+
+    Some(u16::from_le_bytes([header[0], header[1]]))
+}
+
+/// Scans every slot in the region and returns the `(slot, seq)` of the
+/// most recently written valid one, using wrapping comparison so the
+/// sequence number can roll over without losing ordering.
+fn latest_slot<S: Storage>(storage: &S, region_addr: u16) -> Option<(u16, u16)> {
+    let mut latest: Option<(u16, u16)> = None;
+
+    for slot in 0..NUM_SLOTS {
+        if let Some(seq) = read_slot_seq(storage, region_addr, slot) {
+            latest = match latest {
+                Some((_, latest_seq)) if (seq.wrapping_sub(latest_seq) as i16) <= 0 => latest,
+                _ => Some((slot, seq)),
+            };
+        }
+    }
+
+    latest
+}
+
+/// Byte size of one serialized `Counter`: its value, modulus, and
+/// target, each a `u16`. A modulus or target of 0 in storage means
+/// "unset".
+const COUNTER_LEN: u16 = 6;
+
 impl Storable for Counter {
     fn store<S: Storage>(&self, storage: &S, addr: u16) {
         storage.write_bytes(addr, &self.val.to_le_bytes());
+        storage.write_bytes(addr + 2, &self.modulus.unwrap_or(0).to_le_bytes());
+        storage.write_bytes(addr + 4, &self.target.unwrap_or(0).to_le_bytes());
     }
     fn load<S: Storage>(storage: &S, addr: u16) -> Self {
-        let mut buf = [0; 2];
-        storage.read_bytes(addr, 2, &mut buf);
-        Counter::new(u16::from_le_bytes(buf))
+        let mut val_buf = [0; 2];
+        storage.read_bytes(addr, 2, &mut val_buf);
+
+        let mut modulus_buf = [0; 2];
+        storage.read_bytes(addr + 2, 2, &mut modulus_buf);
+        let modulus = u16::from_le_bytes(modulus_buf);
+
+        let mut target_buf = [0; 2];
+        storage.read_bytes(addr + 4, 2, &mut target_buf);
+        let target = u16::from_le_bytes(target_buf);
+
+        Counter {
+            val: u16::from_le_bytes(val_buf),
+            dirty: false,
+            modulus: if modulus == 0 { None } else { Some(modulus) },
+            target: if target == 0 { None } else { Some(target) },
+        }
     }
 }
 
 impl Storable for Counters {
     fn store<S: Storage>(&self, storage: &S, addr: u16) {
         self.a.store(storage, addr);
-        self.b.store(storage, addr + 2);
-        self.c.store(storage, addr + 4);
-        self.d.store(storage, addr + 6);
+        self.b.store(storage, addr + COUNTER_LEN);
+        self.c.store(storage, addr + 2 * COUNTER_LEN);
+        self.d.store(storage, addr + 3 * COUNTER_LEN);
     }
 
     fn load<S: Storage>(storage: &S, addr: u16) -> Self {
         Counters {
             a: Counter::load(storage, addr),
-            b: Counter::load(storage, addr + 2),
-            c: Counter::load(storage, addr + 4),
-            d: Counter::load(storage, addr + 6),
+            b: Counter::load(storage, addr + COUNTER_LEN),
+            c: Counter::load(storage, addr + 2 * COUNTER_LEN),
+            d: Counter::load(storage, addr + 3 * COUNTER_LEN),
         }
     }
 }
 
 impl Storable for State {
     fn store<S: Storage>(&self, storage: &S, addr: u16) {
-        self.counters.store(storage, addr);
+        let (next_slot, next_seq) = match latest_slot(storage, addr) {
+            Some((slot, seq)) => ((slot + 1) % NUM_SLOTS, seq.wrapping_add(1)),
+            None => (0, 0),
+        };
+
+        let base = slot_addr(addr, next_slot);
+        let payload_addr = base + SLOT_HEADER_LEN;
+
+        self.counters.store(storage, payload_addr);
+
+        let mut payload = [0; SLOT_PAYLOAD_LEN as usize];
+        storage.read_bytes(payload_addr, SLOT_PAYLOAD_LEN as usize, &mut payload);
+
+        storage.write_bytes(base, &next_seq.to_le_bytes());
+        storage.write_byte(base + SEQ_LEN, SLOT_MAGIC);
+        storage.write_byte(base + SEQ_LEN + MAGIC_LEN, STATE_SCHEMA_VERSION);
+        storage.write_byte(payload_addr + SLOT_PAYLOAD_LEN, checksum(&payload));
     }
 
     fn load<S: Storage>(storage: &S, addr: u16) -> Self {
-        State {
-            counters: Counters::load(storage, addr),
-            ..Default::default()
+        match latest_slot(storage, addr) {
+            Some((slot, _)) => State {
+                counters: Counters::load(storage, slot_addr(addr, slot) + SLOT_HEADER_LEN),
+                ..Default::default()
+            },
+            None => State::new(),
         }
     }
 }
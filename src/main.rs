@@ -6,6 +6,7 @@ use panic_halt as _;
 use arduino_uno::{pac::EEPROM, prelude::*, Delay};
 use atmega328p_hal::port::{mode, Pin};
 use hd44780_driver::{self as lcd_driver, bus::I2CBus, HD44780};
+use ufmt::uwriteln;
 
 mod display_props {
     pub const DISPLAY_ADDRESS: u8 = 0x27;
@@ -17,11 +18,18 @@ mod display_props {
     pub const COUNTER_START: u8 = 6;
     pub const SELECTED_COUNTER: u8 = BOTTOM_RIGHT;
     pub const DIRTY_STATE: u8 = LINE_WIDTH;
+    pub const ALARM_MARKER: u8 = BOTTOM_RIGHT - 1;
 }
 
 mod eeprom;
+mod serial;
 
 const STATE_STORAGE_ADDRESS: u16 = 1337;
+const SERIAL_BAUD_RATE: u32 = 9600;
+
+/// Main-loop iterations the piezo stays on for; tuned by ear, there's no
+/// hardware timer backing it.
+const BUZZER_TICKS: u16 = 20_000;
 
 use display_props::*;
 use eeprom::Storable;
@@ -60,7 +68,18 @@ fn main() -> ! {
         pins.d9.into_pull_up_input(&pins.ddr).downgrade(),
     ];
 
+    let mut serial = arduino_uno::Serial::new(
+        peripherals.USART0,
+        pins.d0,
+        pins.d1.into_output(&mut pins.ddr),
+        SERIAL_BAUD_RATE.into_baudrate(),
+    );
+
+    let mut piezo = pins.d10.into_output(&mut pins.ddr);
+    let mut buzzer_ticks: u16 = 0;
+
     let mut debouncer = Debouncer::new();
+    let mut line_reader = serial::LineReader::new();
 
     let mut state = State::new();
     avr_device::interrupt::free(|_| {
@@ -72,8 +91,48 @@ fn main() -> ! {
     loop {
         if let Some(input) = debouncer.debounce(Input::from_pins(&mut rows, &cols)) {
             state.handle_input(input);
+            if state.is_alarming() {
+                piezo.set_high().void_unwrap();
+                buzzer_ticks = BUZZER_TICKS;
+            }
             state.update_display(&mut lcd, &mut delay).unwrap();
         }
+
+        if let Ok(byte) = serial.read() {
+            match line_reader.feed(byte) {
+                serial::Fed::Passthrough(byte) => {
+                    if let Some(input) = Input::from_serial(byte) {
+                        state.handle_input(input);
+                        if state.is_alarming() {
+                            piezo.set_high().void_unwrap();
+                            buzzer_ticks = BUZZER_TICKS;
+                        }
+                        state.update_display(&mut lcd, &mut delay).unwrap();
+                    }
+                }
+                serial::Fed::Command(serial::Command::Get) => {
+                    let [a, b, c, d] = state.counters.to_values();
+                    uwriteln!(&mut serial, "{},{},{},{}", a, b, c, d).void_unwrap();
+                }
+                serial::Fed::Command(serial::Command::Set(values)) => {
+                    state.counters.set_all(values);
+                    state.update_display(&mut lcd, &mut delay).unwrap();
+                }
+                serial::Fed::Command(serial::Command::Save) => {
+                    avr_device::interrupt::free(|_| {
+                        state.store(&EEPROM::ptr(), STATE_STORAGE_ADDRESS);
+                    });
+                }
+                serial::Fed::Pending => {}
+            }
+        }
+
+        if buzzer_ticks > 0 {
+            buzzer_ticks -= 1;
+            if buzzer_ticks == 0 {
+                piezo.set_low().void_unwrap();
+            }
+        }
     }
 }
 
@@ -81,6 +140,8 @@ fn main() -> ! {
 enum Mode {
     Normal,
     Input,
+    SetModulus,
+    SetTarget,
     ConfirmReset,
 }
 
@@ -96,6 +157,7 @@ struct State {
     counters: Counters,
     selected_counter: CounterSelection,
     digits_input: Option<DigitsInput>,
+    alarm: bool,
 }
 
 impl State {
@@ -105,15 +167,28 @@ impl State {
             counters: Default::default(),
             selected_counter: CounterSelection::A,
             digits_input: None,
+            alarm: false,
         }
     }
 
+    fn is_alarming(&self) -> bool {
+        self.alarm
+    }
+
     fn change_mode(&mut self, mode: Mode) {
         match mode {
             Mode::Input => {
                 let counter_val = self.get_counter().val();
                 self.digits_input = Some(DigitsInput::new(counter_val));
             }
+            Mode::SetModulus => {
+                let modulus = self.get_counter().modulus().unwrap_or(0);
+                self.digits_input = Some(DigitsInput::new(modulus));
+            }
+            Mode::SetTarget => {
+                let target = self.get_counter().target().unwrap_or(0);
+                self.digits_input = Some(DigitsInput::new(target));
+            }
             Mode::Normal => {
                 self.digits_input = None;
             }
@@ -130,14 +205,51 @@ impl State {
         self.counters.get_mut(self.selected_counter)
     }
 
+    /// Shared `Star`/`Hash`/digit handling for `Input`, `SetModulus`, and
+    /// `SetTarget`; `apply` is how the confirmed value lands on the counter.
+    fn handle_digit_input(&mut self, input: Input, apply: impl FnOnce(&mut Counter, u16)) {
+        if let Some(digits) = self.digits_input.as_mut() {
+            match input {
+                Input::Star => {
+                    if digits.index == 0 {
+                        digits.index = BUF_LEN
+                    }
+
+                    digits.index -= 1;
+                }
+                Input::Hash => {
+                    digits.index = (digits.index + 1) % BUF_LEN;
+                }
+                x => {
+                    if let Some(digit) = x.to_digit() {
+                        digits.add_digit(digit);
+                    } else if let Some(counter_selection) = CounterSelection::from_input(&x) {
+                        if self.selected_counter == counter_selection {
+                            let new_val = digits.parse();
+                            apply(self.get_counter_mut(), new_val);
+                        }
+
+                        self.change_mode(Mode::Normal);
+                    }
+                }
+            }
+        }
+    }
+
     fn handle_input(&mut self, input: Input) {
+        self.alarm = false;
+
         match self.mode {
             Mode::Normal => match input {
                 Input::Num0 => {
                     self.change_mode(Mode::ConfirmReset);
                 }
-                Input::Num1 => {}
-                Input::Num2 => {}
+                Input::Num1 => {
+                    self.change_mode(Mode::SetModulus);
+                }
+                Input::Num2 => {
+                    self.change_mode(Mode::SetTarget);
+                }
                 Input::Num3 => {}
                 Input::Num4 => {}
                 Input::Num5 => {
@@ -150,10 +262,10 @@ impl State {
                 Input::Num8 => {}
                 Input::Num9 => {}
                 Input::Star => {
-                    self.get_counter_mut().dec();
+                    self.counters.dec(self.selected_counter);
                 }
                 Input::Hash => {
-                    self.get_counter_mut().inc();
+                    self.alarm = self.counters.inc(self.selected_counter);
                 }
                 Input::A | Input::B | Input::C | Input::D => {
                     let counter = CounterSelection::from_input(&input).unwrap();
@@ -165,33 +277,17 @@ impl State {
                 }
             },
             Mode::Input => {
-                if let Some(digits) = self.digits_input.as_mut() {
-                    match input {
-                        Input::Star => {
-                            if digits.index == 0 {
-                                digits.index = BUF_LEN
-                            }
-
-                            digits.index -= 1;
-                        }
-                        Input::Hash => {
-                            digits.index = (digits.index + 1) % BUF_LEN;
-                        }
-                        x => {
-                            if let Some(digit) = x.to_digit() {
-                                digits.add_digit(digit);
-                            } else if let Some(counter_selection) = CounterSelection::from_input(&x)
-                            {
-                                if self.selected_counter == counter_selection {
-                                    let new_val = digits.parse();
-                                    self.get_counter_mut().set(new_val);
-                                }
-
-                                self.change_mode(Mode::Normal);
-                            }
-                        }
-                    }
-                }
+                self.handle_digit_input(input, |counter, val| counter.set(val));
+            }
+            Mode::SetModulus => {
+                self.handle_digit_input(input, |counter, val| {
+                    counter.set_modulus(if val == 0 { None } else { Some(val) })
+                });
+            }
+            Mode::SetTarget => {
+                self.handle_digit_input(input, |counter, val| {
+                    counter.set_target(if val == 0 { None } else { Some(val) })
+                });
             }
             Mode::ConfirmReset => match input {
                 Input::Star => {
@@ -227,8 +323,19 @@ impl State {
                         lcd.shift_cursor(lcd_driver::Direction::Right, delay)?;
                     }
                 }
+
+                if let Some(modulus) = self.get_counter().modulus() {
+                    lcd.write_char('/', delay)?;
+                    for c in &Digits::from_u16(modulus).to_chars() {
+                        if let Some(c) = c {
+                            lcd.write_char(*c, delay)?;
+                        } else {
+                            lcd.shift_cursor(lcd_driver::Direction::Right, delay)?;
+                        }
+                    }
+                }
             }
-            Mode::Input => {
+            Mode::Input | Mode::SetModulus | Mode::SetTarget => {
                 lcd.set_cursor_pos(COUNTER_START, delay)?;
                 if let Some(digits_input) = &self.digits_input {
                     for c in &digits_input.buf.to_chars() {
@@ -257,6 +364,11 @@ impl State {
             lcd.write_str("Saved", delay).unwrap();
         }
 
+        if self.get_counter().at_target() {
+            lcd.set_cursor_pos(ALARM_MARKER, delay)?;
+            lcd.write_char('!', delay)?;
+        }
+
         lcd.set_cursor_pos(SELECTED_COUNTER, delay)?;
         lcd.write_char(self.selected_counter.to_char(), delay)?;
 
@@ -297,6 +409,16 @@ impl CounterSelection {
             _ => None,
         }
     }
+
+    /// Next counter in the A->B->C->D carry chain.
+    fn next(&self) -> Option<CounterSelection> {
+        match self {
+            CounterSelection::A => Some(CounterSelection::B),
+            CounterSelection::B => Some(CounterSelection::C),
+            CounterSelection::C => Some(CounterSelection::D),
+            CounterSelection::D => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -325,17 +447,72 @@ impl Counters {
             CounterSelection::D => &mut self.d,
         }
     }
+
+    fn to_values(&self) -> [u16; 4] {
+        [self.a.val(), self.b.val(), self.c.val(), self.d.val()]
+    }
+
+    fn set_all(&mut self, values: [u16; 4]) {
+        self.a.set(values[0]);
+        self.b.set(values[1]);
+        self.c.set(values[2]);
+        self.d.set(values[3]);
+    }
+
+    /// Increments `selection`, cascading a carry down the A->B->C->D
+    /// chain on each modulus rollover. Returns whether `selection` itself,
+    /// not a counter further down the cascade, landed on its target.
+    fn inc(&mut self, selection: CounterSelection) -> bool {
+        let (mut carry, hit_target) = self.get_mut(selection).inc();
+        let mut next = selection.next();
+
+        while carry {
+            match next {
+                Some(selection) => {
+                    carry = self.get_mut(selection).inc().0;
+                    next = selection.next();
+                }
+                None => break,
+            }
+        }
+
+        hit_target
+    }
+
+    /// Decrements `selection`, cascading a borrow the same way `inc`
+    /// cascades a carry.
+    fn dec(&mut self, selection: CounterSelection) {
+        let mut borrow = self.get_mut(selection).dec();
+        let mut next = selection.next();
+
+        while borrow {
+            match next {
+                Some(selection) => {
+                    borrow = self.get_mut(selection).dec();
+                    next = selection.next();
+                }
+                None => break,
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default)]
 struct Counter {
     val: u16,
     dirty: bool,
+    modulus: Option<u16>,
+    target: Option<u16>,
 }
 
 impl Counter {
     fn new(val: u16) -> Counter {
-        Counter { val, dirty: false }
+        Counter {
+            val,
+            dirty: false,
+            modulus: None,
+            target: None,
+        }
     }
 
     fn is_dirty(&self) -> bool {
@@ -350,16 +527,76 @@ impl Counter {
         self.val
     }
 
-    fn inc(&mut self) {
-        self.val = self.val.wrapping_add(1);
+    fn modulus(&self) -> Option<u16> {
+        self.modulus
+    }
+
+    fn set_modulus(&mut self, modulus: Option<u16>) {
+        self.modulus = modulus;
         self.dirty = true;
     }
 
-    fn dec(&mut self) {
-        self.val = self.val.wrapping_sub(1);
+    fn target(&self) -> Option<u16> {
+        self.target
+    }
+
+    fn set_target(&mut self, target: Option<u16>) {
+        self.target = target;
         self.dirty = true;
     }
 
+    fn at_target(&self) -> bool {
+        self.target.map_or(false, |target| self.val == target)
+    }
+
+    /// Increments the counter, returning a carry once its modulus is
+    /// reached (or wrapping at `u16::MAX` with no carry if it has none),
+    /// and whether this increment landed it on its target.
+    fn inc(&mut self) -> (bool, bool) {
+        self.dirty = true;
+
+        let carry = match self.modulus {
+            Some(modulus) if modulus > 0 => {
+                let next = self.val.wrapping_add(1);
+                if next >= modulus {
+                    self.val = 0;
+                    true
+                } else {
+                    self.val = next;
+                    false
+                }
+            }
+            _ => {
+                self.val = self.val.wrapping_add(1);
+                false
+            }
+        };
+
+        (carry, self.at_target())
+    }
+
+    /// Decrements the counter, returning a borrow if it underflows past 0
+    /// with a modulus set, or wrapping at 0 with no borrow if it has none.
+    fn dec(&mut self) -> bool {
+        self.dirty = true;
+
+        match self.modulus {
+            Some(modulus) if modulus > 0 => {
+                if self.val == 0 {
+                    self.val = modulus - 1;
+                    true
+                } else {
+                    self.val -= 1;
+                    false
+                }
+            }
+            _ => {
+                self.val = self.val.wrapping_sub(1);
+                false
+            }
+        }
+    }
+
     fn set(&mut self, val: u16) {
         self.val = val;
         self.dirty = true;
@@ -464,7 +701,6 @@ impl Input {
         None
     }
 
-    #[allow(dead_code)]
     fn from_serial(byte: u8) -> Option<Input> {
         use Input::*;
 
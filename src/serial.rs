@@ -0,0 +1,116 @@
+//! Line-oriented command protocol layered on top of the single-byte key
+//! mapping in `Input::from_serial`, so a host PC can back up, restore, or
+//! log the counters over USART instead of (or alongside) the keypad.
+
+/// Worst-case length of a `Set` command's payload: four `u16` fields at
+/// up to 5 digits each plus the 3 separating commas (`65535,65535,65535,65535`).
+const LINE_BUF_LEN: usize = 23;
+
+/// A command parsed out of a completed line of serial input.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// `G` - dump the four counter values as a CSV line.
+    Get,
+    /// `S<a>,<b>,<c>,<d>` - set all four counter values at once.
+    Set([u16; 4]),
+    /// `W` - force a save to EEPROM.
+    Save,
+}
+
+/// What `LineReader::feed` did with an incoming byte.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Fed {
+    /// A command line completed and parsed successfully.
+    Command(Command),
+    /// Not part of a command line; hand the byte to `Input::from_serial`.
+    Passthrough(u8),
+    /// Part of a command line still being accumulated, or the terminator
+    /// of a malformed or overlong line, which is discarded.
+    Pending,
+}
+
+fn parse_set(line: &[u8]) -> Option<[u16; 4]> {
+    let mut values = [0u16; 4];
+    let mut field = 0;
+    let mut have_digit = false;
+
+    for &byte in line {
+        match byte {
+            b'0'..=b'9' => {
+                have_digit = true;
+                values[field] = values[field]
+                    .saturating_mul(10)
+                    .saturating_add((byte - b'0') as u16);
+            }
+            b',' => {
+                if !have_digit || field == 3 {
+                    return None;
+                }
+                field += 1;
+                have_digit = false;
+            }
+            _ => return None,
+        }
+    }
+
+    if field == 3 && have_digit {
+        Some(values)
+    } else {
+        None
+    }
+}
+
+/// Accumulates incoming bytes into command lines, mirroring how the
+/// keypad `Debouncer` only yields on a changed reading.
+#[derive(Debug, Default)]
+pub struct LineReader {
+    buf: [u8; LINE_BUF_LEN],
+    len: usize,
+    capturing: bool,
+}
+
+impl LineReader {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Feeds a single incoming byte. `G` and `W` are single-byte commands
+    /// recognized immediately; `S` starts capturing the rest of the line
+    /// up to its terminator. Anything else is passed through unchanged
+    /// for `Input::from_serial` to interpret as a key.
+    pub fn feed(&mut self, byte: u8) -> Fed {
+        if self.capturing {
+            if byte == b'\n' || byte == b'\r' {
+                self.capturing = false;
+                let line = self.buf.get(..self.len).and_then(parse_set);
+                self.len = 0;
+                return match line {
+                    Some(values) => Fed::Command(Command::Set(values)),
+                    None => Fed::Pending,
+                };
+            }
+
+            if self.len < LINE_BUF_LEN {
+                self.buf[self.len] = byte;
+                self.len += 1;
+            } else {
+                // Line too long; drop it and wait for the next terminator.
+                // A length past the buffer makes the terminator discard it.
+                self.len = LINE_BUF_LEN + 1;
+            }
+
+            return Fed::Pending;
+        }
+
+        match byte {
+            b'G' => Fed::Command(Command::Get),
+            b'W' => Fed::Command(Command::Save),
+            b'S' => {
+                self.capturing = true;
+                self.len = 0;
+                Fed::Pending
+            }
+            _ => Fed::Passthrough(byte),
+        }
+    }
+}